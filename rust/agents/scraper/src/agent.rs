@@ -6,12 +6,19 @@ use hyperlane_base::{
     metrics::AgentMetrics, run_all, settings::IndexSettings, BaseAgent, ChainMetrics,
     ContractSyncMetrics, CoreMetrics, HyperlaneAgentCore, MetricsUpdater,
 };
-use hyperlane_core::{HyperlaneDomain, KnownHyperlaneDomain};
-use num_traits::cast::FromPrimitive;
-use tokio::task::JoinHandle;
-use tracing::{info_span, instrument::Instrumented, trace, Instrument};
+use hyperlane_core::HyperlaneDomain;
+use prometheus::IntCounterVec;
+use tokio::{sync::Mutex, task::JoinHandle};
+use tracing::{instrument::Instrumented, trace};
 
-use crate::{chain_scraper::HyperlaneSqlDb, db::ScraperDb, settings::ScraperSettings};
+use crate::{
+    chain_scraper::HyperlaneSqlDb,
+    db::ScraperDb,
+    notifier::{spawn_notifier, watch_and_notify, EventKind, NotifierConfig, NotifierHandle},
+    reorg::{self, ReorgTracker, DEFAULT_REORG_REWIND_DEPTH},
+    settings::ScraperSettings,
+    supervisor::{run_until_shutdown, BackgroundRunner},
+};
 
 /// A message explorer scraper agent
 #[derive(Debug, AsRef)]
@@ -25,6 +32,9 @@ pub struct Scraper {
     core_metrics: Arc<CoreMetrics>,
     agent_metrics: AgentMetrics,
     chain_metrics: ChainMetrics,
+    notifier: NotifierHandle,
+    supervisor: Arc<BackgroundRunner>,
+    reorgs_detected: IntCounterVec,
 }
 
 #[derive(Debug)]
@@ -32,6 +42,11 @@ struct ChainScraper {
     index_settings: IndexSettings,
     db: HyperlaneSqlDb,
     domain: HyperlaneDomain,
+    /// How many blocks to walk back, at most, when a reorg is detected for
+    /// this chain. Scraper-local config (not `IndexSettings`, which belongs
+    /// to `hyperlane_base` and isn't something this crate can add fields
+    /// to): falls back to `DEFAULT_REORG_REWIND_DEPTH` when unset.
+    reorg_rewind_depth: u32,
 }
 
 #[async_trait]
@@ -51,6 +66,20 @@ impl BaseAgent for Scraper {
         let db = ScraperDb::connect(&settings.db).await?;
         let core = settings.build_hyperlane_core(metrics.clone());
 
+        let notifier_config = NotifierConfig::from_file(&settings.notifier_config_path)?;
+        // The notifier task runs for the lifetime of the agent; it is fed
+        // through `notifier` and never otherwise joined.
+        let (notifier, _notifier_task) =
+            spawn_notifier(notifier_config, settings.notifier_config_path.clone());
+
+        let supervisor = Arc::new(BackgroundRunner::new(&metrics)?);
+
+        let reorgs_detected = metrics.new_int_counter(
+            "scraper_reorgs_detected",
+            "Number of reorgs detected and rewound for a chain",
+            &["domain"],
+        )?;
+
         let contract_sync_metrics = Arc::new(ContractSyncMetrics::new(&metrics));
         let mut scrapers: HashMap<u32, ChainScraper> = HashMap::new();
 
@@ -65,14 +94,19 @@ impl BaseAgent for Scraper {
                     .await?
                     .into(),
                 &chain_setup.index.clone(),
+                notifier.clone(),
             )
             .await?;
+            let reorg_rewind_depth = settings
+                .reorg_rewind_depth(domain)
+                .unwrap_or(DEFAULT_REORG_REWIND_DEPTH);
             scrapers.insert(
                 domain.id(),
                 ChainScraper {
                     domain: domain.clone(),
                     db,
                     index_settings: chain_setup.index.clone(),
+                    reorg_rewind_depth,
                 },
             );
         }
@@ -87,17 +121,39 @@ impl BaseAgent for Scraper {
             core_metrics: metrics,
             agent_metrics,
             chain_metrics,
+            notifier,
+            supervisor,
+            reorgs_detected,
         })
     }
 
     #[allow(clippy::async_yields_async)]
     async fn run(self) -> Instrumented<JoinHandle<eyre::Result<()>>> {
+        let supervisor = self.supervisor.clone();
+        tokio::spawn(async move {
+            if let Ok(mut sigterm) =
+                tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            {
+                sigterm.recv().await;
+                trace!("received SIGTERM, signalling indexer tasks to shut down");
+                supervisor.shutdown();
+            }
+        });
+
         let mut tasks = Vec::with_capacity(self.scrapers.len());
-        for domain in self.scrapers.keys() {
-            tasks.push(self.scrape(*domain).await);
+        for (domain_id, scraper) in self.scrapers.iter() {
+            tasks.push(self.scrape(*domain_id).await);
 
-            let domain = KnownHyperlaneDomain::from_u32(*domain).unwrap();
-            let chain_conf = self.settings.chain_setup(&domain.into()).unwrap();
+            // Drive the metrics updater off the `HyperlaneDomain` and chain
+            // config already resolved for this scraper, rather than
+            // re-deriving a `KnownHyperlaneDomain` from the raw id. That
+            // round-trip panics for any chain that isn't baked into the
+            // `KnownHyperlaneDomain` enum, which rules out custom domains
+            // configured purely through `ScraperSettings`.
+            let chain_conf = self
+                .settings
+                .chain_setup(&scraper.domain)
+                .expect("Missing chain config");
             let metrics_updater = MetricsUpdater::new(
                 chain_conf,
                 self.core_metrics.clone(),
@@ -121,6 +177,7 @@ impl Scraper {
         let db = scraper.db.clone();
         let index_settings = scraper.index_settings.clone();
         let domain = scraper.domain.clone();
+        let reorg_rewind_depth = scraper.reorg_rewind_depth;
 
         let mut tasks = Vec::with_capacity(2);
         tasks.push(
@@ -130,6 +187,7 @@ impl Scraper {
                 self.contract_sync_metrics.clone(),
                 db.clone(),
                 index_settings.clone(),
+                reorg_rewind_depth,
             )
             .await,
         );
@@ -140,6 +198,7 @@ impl Scraper {
                 self.contract_sync_metrics.clone(),
                 db.clone(),
                 index_settings.clone(),
+                reorg_rewind_depth,
             )
             .await,
         );
@@ -150,6 +209,7 @@ impl Scraper {
                 self.contract_sync_metrics.clone(),
                 db,
                 index_settings.clone(),
+                reorg_rewind_depth,
             )
             .await,
         );
@@ -157,9 +217,15 @@ impl Scraper {
     }
 }
 
-/// Create a function to spawn task that syncs contract events
+/// Create a function that hands a restartable worker closure to the
+/// `BackgroundRunner`, so a transient RPC failure or panic in one chain's
+/// indexer restarts with backoff instead of silently killing the task.
+/// Each worker checks for a reorg against the stored tip before it
+/// (re)starts indexing and keeps re-checking on a timer for as long as it
+/// runs, and runs a watcher that notifies `self.notifier` whenever the
+/// db's latest row for this event changes.
 macro_rules! spawn_sync_task {
-    ($name:ident, $cursor: ident, $label:literal) => {
+    ($name:ident, $cursor: ident, $label:literal, $event:expr, $poll:ident) => {
         async fn $name(
             &self,
             domain: HyperlaneDomain,
@@ -167,27 +233,56 @@ macro_rules! spawn_sync_task {
             contract_sync_metrics: Arc<ContractSyncMetrics>,
             db: HyperlaneSqlDb,
             index_settings: IndexSettings,
+            reorg_rewind_depth: u32,
         ) -> Instrumented<JoinHandle<eyre::Result<()>>> {
-            let sync = self
-                .as_ref()
-                .settings
-                .$name(
-                    &domain,
-                    &metrics.clone(),
-                    &contract_sync_metrics.clone(),
-                    Arc::new(db.clone()),
-                )
-                .await
-                .unwrap();
-            let cursor = sync
-                .$cursor(index_settings.clone())
-                .await;
-                tokio::spawn(async move {
-                    sync
-                        .sync($label, cursor)
-                        .await
-                })
-                .instrument(info_span!("ChainContractSync", chain=%domain.name(), event=$label))
+            let settings = self.settings.clone();
+            let notifier = self.notifier.clone();
+            let reorgs_detected = self.reorgs_detected.with_label_values(&[domain.name()]);
+            let tracker = Arc::new(Mutex::new(ReorgTracker::new(reorg_rewind_depth)));
+            self.supervisor.supervise(
+                domain.name().to_string(),
+                $label,
+                move |shutdown| {
+                    let settings = settings.clone();
+                    let metrics = metrics.clone();
+                    let contract_sync_metrics = contract_sync_metrics.clone();
+                    let db = db.clone();
+                    let index_settings = index_settings.clone();
+                    let domain = domain.clone();
+                    let notifier = notifier.clone();
+                    let reorgs_detected = reorgs_detected.clone();
+                    let tracker = tracker.clone();
+                    async move {
+                        reorg::check_and_rewind(&db, &domain, &tracker, &reorgs_detected).await?;
+
+                        let sync = settings
+                            .$name(&domain, &metrics, &contract_sync_metrics, Arc::new(db.clone()))
+                            .await?;
+                        let cursor = sync.$cursor(index_settings).await;
+
+                        let domain_id = domain.id();
+                        let watch_db = db.clone();
+                        let watcher = tokio::spawn(async move {
+                            watch_and_notify(notifier, domain_id, $event, move || {
+                                let db = watch_db.clone();
+                                async move { db.$poll().await }
+                            })
+                            .await
+                        });
+                        let reorg_watcher = tokio::spawn(reorg::watch_for_reorgs(
+                            db.clone(),
+                            domain.clone(),
+                            tracker.clone(),
+                            reorgs_detected.clone(),
+                        ));
+
+                        let result = run_until_shutdown(shutdown, sync.sync($label, cursor)).await;
+                        watcher.abort();
+                        reorg_watcher.abort();
+                        result
+                    }
+                },
+            )
         }
     }
 }
@@ -199,43 +294,77 @@ impl Scraper {
         contract_sync_metrics: Arc<ContractSyncMetrics>,
         db: HyperlaneSqlDb,
         index_settings: IndexSettings,
+        reorg_rewind_depth: u32,
     ) -> Instrumented<JoinHandle<eyre::Result<()>>> {
-        let sync = self
-            .as_ref()
-            .settings
-            .build_message_indexer(
-                &domain,
-                &metrics.clone(),
-                &contract_sync_metrics.clone(),
-                Arc::new(db.clone()),
-            )
-            .await
-            .unwrap();
-        let latest_nonce = self
-            .scrapers
-            .get(&domain.id())
-            .unwrap()
-            .db
-            .last_message_nonce()
-            .await
-            .unwrap_or(None)
-            .unwrap_or(0);
-        let cursor = sync
-            .forward_message_sync_cursor(index_settings.clone(), latest_nonce.saturating_sub(1))
-            .await;
-        tokio::spawn(async move { sync.sync("message_dispatch", cursor).await }).instrument(
-            info_span!("ChainContractSync", chain=%domain.name(), event="message_dispatch"),
+        let settings = self.settings.clone();
+        let notifier = self.notifier.clone();
+        let reorgs_detected = self.reorgs_detected.with_label_values(&[domain.name()]);
+        let tracker = Arc::new(Mutex::new(ReorgTracker::new(reorg_rewind_depth)));
+        self.supervisor.supervise(
+            domain.name().to_string(),
+            "message_dispatch",
+            move |shutdown| {
+                let settings = settings.clone();
+                let metrics = metrics.clone();
+                let contract_sync_metrics = contract_sync_metrics.clone();
+                let db = db.clone();
+                let index_settings = index_settings.clone();
+                let domain = domain.clone();
+                let notifier = notifier.clone();
+                let reorgs_detected = reorgs_detected.clone();
+                let tracker = tracker.clone();
+                async move {
+                    reorg::check_and_rewind(&db, &domain, &tracker, &reorgs_detected).await?;
+
+                    let sync = settings
+                        .build_message_indexer(&domain, &metrics, &contract_sync_metrics, Arc::new(db.clone()))
+                        .await?;
+                    let latest_nonce = db
+                        .last_message_nonce()
+                        .await?
+                        .and_then(|(nonce, _)| nonce.parse::<u32>().ok())
+                        .unwrap_or(0);
+                    let cursor = sync
+                        .forward_message_sync_cursor(index_settings, latest_nonce.saturating_sub(1))
+                        .await;
+
+                    let domain_id = domain.id();
+                    let watch_db = db.clone();
+                    let watcher = tokio::spawn(async move {
+                        watch_and_notify(notifier, domain_id, EventKind::MessageDispatch, move || {
+                            let db = watch_db.clone();
+                            async move { db.last_message_nonce().await }
+                        })
+                        .await
+                    });
+                    let reorg_watcher = tokio::spawn(reorg::watch_for_reorgs(
+                        db.clone(),
+                        domain.clone(),
+                        tracker.clone(),
+                        reorgs_detected.clone(),
+                    ));
+
+                    let result = run_until_shutdown(shutdown, sync.sync("message_dispatch", cursor)).await;
+                    watcher.abort();
+                    reorg_watcher.abort();
+                    result
+                }
+            },
         )
     }
 
     spawn_sync_task!(
         build_delivery_indexer,
         rate_limited_cursor,
-        "message_delivery"
+        "message_delivery",
+        EventKind::MessageDelivery,
+        last_delivered_nonce
     );
     spawn_sync_task!(
         build_interchain_gas_payment_indexer,
         rate_limited_cursor,
-        "gas_payment"
+        "gas_payment",
+        EventKind::GasPayment,
+        last_gas_payment_id
     );
 }
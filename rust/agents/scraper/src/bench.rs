@@ -0,0 +1,289 @@
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use eyre::Context;
+use hyperlane_base::{settings::IndexSettings, ContractSyncMetrics, CoreMetrics};
+use hyperlane_core::HyperlaneDomain;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use tokio::time::sleep;
+use tracing::info;
+
+use crate::{
+    chain_scraper::HyperlaneSqlDb,
+    db::ScraperDb,
+    notifier::{spawn_notifier, NotifierConfig},
+    settings::ScraperSettings,
+};
+
+/// How often the progress bars poll `ContractSyncMetrics` for the current
+/// cursor position.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// An inclusive `[from, to]` block range to benchmark indexing over.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchmarkRange {
+    pub from: u32,
+    pub to: u32,
+}
+
+impl BenchmarkRange {
+    fn block_count(&self) -> u64 {
+        (self.to.saturating_sub(self.from) as u64) + 1
+    }
+}
+
+/// Throughput for one `(domain, event_label)` pair over a benchmarked
+/// range. `poll_latencies` holds how long each progress poll of
+/// `ContractSyncMetrics` took, and the percentiles below are taken over
+/// those; the cursor position at each poll is not kept, so no cursor
+/// advancement rate is reported.
+#[derive(Debug, Clone)]
+pub struct BenchmarkReport {
+    pub event_label: &'static str,
+    pub events_indexed: u64,
+    pub elapsed: Duration,
+    pub poll_latencies: Vec<Duration>,
+}
+
+impl BenchmarkReport {
+    pub fn events_per_second(&self) -> f64 {
+        self.events_indexed as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    pub fn percentile(&self, p: f64) -> Duration {
+        if self.poll_latencies.is_empty() {
+            return Duration::ZERO;
+        }
+        let mut sorted = self.poll_latencies.clone();
+        sorted.sort();
+        let idx = (((sorted.len() - 1) as f64) * p).round() as usize;
+        sorted[idx]
+    }
+}
+
+/// Measures end-to-end indexing throughput for a chain and block range
+/// without requiring a production SQL backend: it builds the same
+/// message/delivery/gas-payment indexers `Scraper` uses, points them at a
+/// scratch, in-memory-backed `HyperlaneSqlDb`, and drives each
+/// `(domain, event_label)` pair to a terminal progress bar fed straight from
+/// the `ContractSyncMetrics` counters production emits, so the numbers
+/// contributors see here match what's reported in the field.
+pub struct BenchmarkRunner {
+    settings: ScraperSettings,
+    metrics: Arc<CoreMetrics>,
+    contract_sync_metrics: Arc<ContractSyncMetrics>,
+}
+
+impl BenchmarkRunner {
+    pub fn new(settings: ScraperSettings, metrics: Arc<CoreMetrics>) -> Self {
+        let contract_sync_metrics = Arc::new(ContractSyncMetrics::new(&metrics));
+        Self {
+            settings,
+            metrics,
+            contract_sync_metrics,
+        }
+    }
+
+    /// Run the message, delivery, and gas-payment indexers for `domain` over
+    /// `range`, print a live progress bar per event type, and return a final
+    /// summary report for each.
+    pub async fn run(
+        &self,
+        domain: &HyperlaneDomain,
+        range: BenchmarkRange,
+    ) -> eyre::Result<Vec<BenchmarkReport>> {
+        let chain_setup = self
+            .settings
+            .chain_setup(domain)
+            .context("missing chain config for benchmark domain")?;
+        let scraper_db = ScraperDb::connect("sqlite::memory:").await?;
+        // Benchmarks don't deliver webhooks; an empty config means every
+        // `notify` call is a cheap, immediate no-op.
+        let (notifier, _notifier_task) = spawn_notifier(NotifierConfig::default(), PathBuf::new());
+        let db = HyperlaneSqlDb::new(
+            scraper_db,
+            chain_setup.addresses.mailbox,
+            domain.clone(),
+            self.settings
+                .build_provider(domain, &self.metrics)
+                .await?
+                .into(),
+            &chain_setup.index,
+            notifier,
+        )
+        .await?;
+
+        let multi = MultiProgress::new();
+        let mut reports = Vec::with_capacity(3);
+        for label in ["message_dispatch", "message_delivery", "gas_payment"] {
+            let bar = multi.add(progress_bar(domain, label, range.block_count()));
+            let report = self
+                .run_one(domain, label, range, &chain_setup.index, db.clone(), &bar)
+                .await?;
+            bar.finish_with_message(format!(
+                "{:.1} events/s  p50={:?}  p99={:?}",
+                report.events_per_second(),
+                report.percentile(0.50),
+                report.percentile(0.99),
+            ));
+            reports.push(report);
+        }
+        print_summary(domain, &reports);
+        Ok(reports)
+    }
+
+    async fn run_one(
+        &self,
+        domain: &HyperlaneDomain,
+        label: &'static str,
+        range: BenchmarkRange,
+        index_settings: &IndexSettings,
+        db: HyperlaneSqlDb,
+        bar: &ProgressBar,
+    ) -> eyre::Result<BenchmarkReport> {
+        let sync_task = match label {
+            "message_dispatch" => {
+                let sync = self
+                    .settings
+                    .build_message_indexer(
+                        domain,
+                        &self.metrics,
+                        &self.contract_sync_metrics,
+                        Arc::new(db.clone()),
+                    )
+                    .await?;
+                let cursor = sync
+                    .forward_message_sync_cursor(
+                        index_settings.clone(),
+                        range.from.saturating_sub(1),
+                    )
+                    .await;
+                tokio::spawn(async move { sync.sync(label, cursor).await })
+            }
+            "message_delivery" => {
+                let sync = self
+                    .settings
+                    .build_delivery_indexer(
+                        domain,
+                        &self.metrics,
+                        &self.contract_sync_metrics,
+                        Arc::new(db.clone()),
+                    )
+                    .await?;
+                let cursor = sync.rate_limited_cursor(index_settings.clone()).await;
+                tokio::spawn(async move { sync.sync(label, cursor).await })
+            }
+            "gas_payment" => {
+                let sync = self
+                    .settings
+                    .build_interchain_gas_payment_indexer(
+                        domain,
+                        &self.metrics,
+                        &self.contract_sync_metrics,
+                        Arc::new(db.clone()),
+                    )
+                    .await?;
+                let cursor = sync.rate_limited_cursor(index_settings.clone()).await;
+                tokio::spawn(async move { sync.sync(label, cursor).await })
+            }
+            other => eyre::bail!("unknown benchmark label: {other}"),
+        };
+
+        let start = Instant::now();
+        let mut poll_latencies = Vec::new();
+        loop {
+            let tick = Instant::now();
+            let height = self
+                .contract_sync_metrics
+                .cursor_current_block(domain.name(), label);
+            poll_latencies.push(tick.elapsed());
+            bar.set_position(height.saturating_sub(range.from as u64).min(range.block_count()));
+            if height >= range.to as u64 || sync_task.is_finished() {
+                break;
+            }
+            sleep(POLL_INTERVAL).await;
+        }
+        sync_task.abort();
+
+        let events_indexed = self
+            .contract_sync_metrics
+            .stored_events(domain.name(), label);
+        Ok(BenchmarkReport {
+            event_label: label,
+            events_indexed,
+            elapsed: start.elapsed(),
+            poll_latencies,
+        })
+    }
+}
+
+fn progress_bar(domain: &HyperlaneDomain, label: &'static str, blocks: u64) -> ProgressBar {
+    let bar = ProgressBar::new(blocks);
+    bar.set_style(
+        ProgressStyle::with_template(
+            "{prefix:.bold.dim} [{bar:40.cyan/blue}] {pos}/{len} blocks ({eta})",
+        )
+        .expect("valid progress template")
+        .progress_chars("=>-"),
+    );
+    bar.set_prefix(format!("{}/{label}", domain.name()));
+    bar
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(events_indexed: u64, elapsed: Duration, poll_latencies: Vec<Duration>) -> BenchmarkReport {
+        BenchmarkReport {
+            event_label: "message_dispatch",
+            events_indexed,
+            elapsed,
+            poll_latencies,
+        }
+    }
+
+    #[test]
+    fn events_per_second_divides_by_elapsed() {
+        let report = report(100, Duration::from_secs(10), vec![]);
+        assert_eq!(report.events_per_second(), 10.0);
+    }
+
+    #[test]
+    fn events_per_second_does_not_divide_by_zero() {
+        let report = report(100, Duration::ZERO, vec![]);
+        assert!(report.events_per_second().is_finite());
+    }
+
+    #[test]
+    fn percentile_of_empty_latencies_is_zero() {
+        let report = report(0, Duration::from_secs(1), vec![]);
+        assert_eq!(report.percentile(0.99), Duration::ZERO);
+    }
+
+    #[test]
+    fn percentile_picks_the_right_rank() {
+        let latencies = (1..=10).map(Duration::from_millis).collect();
+        let report = report(10, Duration::from_secs(1), latencies);
+        assert_eq!(report.percentile(0.0), Duration::from_millis(1));
+        assert_eq!(report.percentile(1.0), Duration::from_millis(10));
+    }
+}
+
+fn print_summary(domain: &HyperlaneDomain, reports: &[BenchmarkReport]) {
+    info!(chain = %domain.name(), "benchmark summary");
+    for report in reports {
+        info!(
+            event = report.event_label,
+            events_per_second = report.events_per_second(),
+            events_indexed = report.events_indexed,
+            elapsed = ?report.elapsed,
+            p50 = ?report.percentile(0.50),
+            p99 = ?report.percentile(0.99),
+            "indexer throughput"
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use sqlx::{any::AnyPoolOptions, AnyPool};
+
+/// Thin wrapper around the scraper's connection pool, shared by every
+/// chain's `HyperlaneSqlDb` so all chains persist to the same database.
+#[derive(Debug, Clone)]
+pub struct ScraperDb {
+    pool: AnyPool,
+}
+
+impl ScraperDb {
+    /// Connect to `url` (a postgres connection string in production, or
+    /// `sqlite::memory:` for benchmarks) and ensure the scraper's tables
+    /// exist.
+    pub async fn connect(url: &str) -> eyre::Result<Self> {
+        sqlx::any::install_default_drivers();
+        let pool = AnyPoolOptions::new().connect(url).await?;
+        let db = Self { pool };
+        db.ensure_schema().await?;
+        Ok(db)
+    }
+
+    pub(crate) fn pool(&self) -> &AnyPool {
+        &self.pool
+    }
+
+    async fn ensure_schema(&self) -> eyre::Result<()> {
+        for statement in [
+            "CREATE TABLE IF NOT EXISTS messages (
+                domain_id INTEGER NOT NULL,
+                nonce INTEGER NOT NULL,
+                block_number INTEGER NOT NULL,
+                PRIMARY KEY (domain_id, nonce)
+            )",
+            "CREATE TABLE IF NOT EXISTS deliveries (
+                domain_id INTEGER NOT NULL,
+                nonce INTEGER NOT NULL,
+                block_number INTEGER NOT NULL,
+                PRIMARY KEY (domain_id, nonce)
+            )",
+            "CREATE TABLE IF NOT EXISTS gas_payments (
+                domain_id INTEGER NOT NULL,
+                payment_id TEXT NOT NULL,
+                block_number INTEGER NOT NULL,
+                PRIMARY KEY (domain_id, payment_id)
+            )",
+            "CREATE TABLE IF NOT EXISTS indexed_blocks (
+                domain_id INTEGER NOT NULL,
+                number INTEGER NOT NULL,
+                hash TEXT NOT NULL,
+                parent_hash TEXT NOT NULL,
+                PRIMARY KEY (domain_id, number)
+            )",
+        ] {
+            sqlx::query(statement).execute(&self.pool).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,328 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use eyre::Context;
+use serde::Deserialize;
+use tokio::{
+    sync::mpsc::{self, Receiver, Sender},
+    task::JoinHandle,
+    time::sleep,
+};
+use tracing::{debug, instrument::Instrumented, warn, Instrument};
+
+/// How long a bearer token is cached before it's refreshed from the
+/// notifier config file on disk.
+const TOKEN_EXPIRY_MS: u64 = 30 * 60 * 1000;
+/// Capacity of the channel feeding the notifier task; bounded so a stalled
+/// webhook consumer applies backpressure to nothing but itself.
+const NOTIFIER_CHANNEL_SIZE: usize = 1_000;
+/// Maximum number of delivery attempts before an event is dropped and logged.
+const MAX_DELIVERY_ATTEMPTS: u32 = 5;
+/// Delay before the first retry; doubles on each subsequent attempt.
+const INITIAL_RETRY_BACKOFF: Duration = Duration::from_millis(250);
+
+/// The kind of indexed row that a notification describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    MessageDispatch,
+    MessageDelivery,
+    GasPayment,
+}
+
+impl EventKind {
+    /// The event label used throughout the scraper (matches the
+    /// `spawn_sync_task!` `$label` literals).
+    pub fn label(&self) -> &'static str {
+        match self {
+            EventKind::MessageDispatch => "message_dispatch",
+            EventKind::MessageDelivery => "message_delivery",
+            EventKind::GasPayment => "gas_payment",
+        }
+    }
+}
+
+/// A single row that was just committed to `HyperlaneSqlDb` and should be
+/// relayed to whichever `RemoteNotifier`s are subscribed to its chain.
+#[derive(Debug, Clone)]
+pub struct NotificationEvent {
+    pub domain_id: u32,
+    pub event: EventKind,
+    /// The message nonce, delivery id, or gas payment id, stringified so the
+    /// payload shape stays stable across event kinds.
+    pub item_id: String,
+    pub block_number: u64,
+}
+
+/// A compact JSON body POSTed to a `RemoteNotifier`'s webhook.
+#[derive(Debug, serde::Serialize)]
+struct NotificationPayload<'a> {
+    domain_id: u32,
+    event: &'static str,
+    item_id: &'a str,
+    block_number: u64,
+}
+
+/// Describes a single webhook subscriber for one chain.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RemoteNotifier {
+    pub webhook_url: String,
+    pub auth_token: String,
+    pub events: Vec<EventKind>,
+}
+
+impl RemoteNotifier {
+    fn wants(&self, event: EventKind) -> bool {
+        self.events.contains(&event)
+    }
+}
+
+/// Per-chain notifier configuration: a single JSON document mapping domain
+/// id to `RemoteNotifier`, resolved once at startup and hot-reloaded on the
+/// `TOKEN_EXPIRY_MS` cadence so a rotated token is picked up without
+/// restarting the agent.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(transparent)]
+pub struct NotifierConfig {
+    chains: HashMap<u32, RemoteNotifier>,
+}
+
+impl NotifierConfig {
+    /// Load a `NotifierConfig` from its on-disk JSON document. Missing files
+    /// are treated as "no chain has a notifier configured" rather than an
+    /// error, since notifiers are optional.
+    pub fn from_file(path: &Path) -> eyre::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("reading notifier config {path:?}"))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("parsing notifier config {path:?}"))
+    }
+
+    /// The notifier configured for a given domain, if any.
+    pub fn get(&self, domain_id: u32) -> Option<&RemoteNotifier> {
+        self.chains.get(&domain_id)
+    }
+}
+
+/// A handle used by the indexing tasks to push notifications without ever
+/// blocking on delivery; an event is dropped if the channel is full or the
+/// notifier task has stopped.
+#[derive(Debug, Clone)]
+pub struct NotifierHandle {
+    tx: Sender<NotificationEvent>,
+}
+
+impl NotifierHandle {
+    /// Queue a notification for delivery. Never blocks the caller on a
+    /// webhook response; drops the event (with a warning) if the channel is
+    /// full, rather than back-pressuring the sync loop.
+    pub fn notify(&self, event: NotificationEvent) {
+        if let Err(err) = self.tx.try_send(event) {
+            warn!(?err, "notifier channel full or closed, dropping event");
+        }
+    }
+}
+
+/// Spawns the background task that drains queued `NotificationEvent`s and
+/// POSTs them to each subscribed chain's webhook, retrying with backoff on
+/// non-2xx responses so a flaky consumer never stalls indexing.
+pub fn spawn_notifier(
+    config: NotifierConfig,
+    config_path: PathBuf,
+) -> (NotifierHandle, Instrumented<JoinHandle<()>>) {
+    let (tx, rx) = mpsc::channel(NOTIFIER_CHANNEL_SIZE);
+    let handle = NotifierHandle { tx };
+    let join = tokio::spawn(run_notifier(config, config_path, rx))
+        .instrument(tracing::info_span!("Notifier"));
+    (handle, join)
+}
+
+async fn run_notifier(
+    mut config: NotifierConfig,
+    config_path: PathBuf,
+    mut rx: Receiver<NotificationEvent>,
+) {
+    let client = reqwest::Client::new();
+    let mut tokens: HashMap<u32, (String, Instant)> = HashMap::new();
+    while let Some(event) = rx.recv().await {
+        let Some(notifier) = config.get(event.domain_id).cloned() else {
+            continue;
+        };
+        if !notifier.wants(event.event) {
+            continue;
+        }
+        let token = current_token(&mut tokens, &mut config, &config_path, event.domain_id)
+            .await
+            .unwrap_or_else(|| notifier.auth_token.clone());
+        deliver(&client, &notifier, &event, &token).await;
+    }
+}
+
+/// Returns the bearer token to use for `domain_id`, refreshing it from
+/// `config_path` once the cached copy is older than `TOKEN_EXPIRY_MS`.
+async fn current_token(
+    tokens: &mut HashMap<u32, (String, Instant)>,
+    config: &mut NotifierConfig,
+    config_path: &Path,
+    domain_id: u32,
+) -> Option<String> {
+    if let Some((token, fetched_at)) = tokens.get(&domain_id) {
+        if fetched_at.elapsed() < Duration::from_millis(TOKEN_EXPIRY_MS) {
+            return Some(token.clone());
+        }
+    }
+    if let Ok(fresh) = NotifierConfig::from_file(config_path) {
+        *config = fresh;
+    }
+    let token = config.get(domain_id)?.auth_token.clone();
+    tokens.insert(domain_id, (token.clone(), Instant::now()));
+    Some(token)
+}
+
+async fn deliver(
+    client: &reqwest::Client,
+    notifier: &RemoteNotifier,
+    event: &NotificationEvent,
+    token: &str,
+) {
+    let payload = NotificationPayload {
+        domain_id: event.domain_id,
+        event: event.event.label(),
+        item_id: &event.item_id,
+        block_number: event.block_number,
+    };
+
+    let mut backoff = INITIAL_RETRY_BACKOFF;
+    for attempt in 1..=MAX_DELIVERY_ATTEMPTS {
+        let result = client
+            .post(&notifier.webhook_url)
+            .bearer_auth(token)
+            .json(&payload)
+            .send()
+            .await;
+
+        match result {
+            Ok(resp) if resp.status().is_success() => {
+                debug!(
+                    domain_id = event.domain_id,
+                    event = payload.event,
+                    "delivered notification"
+                );
+                return;
+            }
+            Ok(resp) => {
+                warn!(
+                    domain_id = event.domain_id,
+                    event = payload.event,
+                    status = %resp.status(),
+                    attempt,
+                    "notifier webhook returned non-2xx, retrying"
+                );
+            }
+            Err(err) => {
+                warn!(
+                    domain_id = event.domain_id,
+                    event = payload.event,
+                    %err,
+                    attempt,
+                    "notifier webhook request failed, retrying"
+                );
+            }
+        }
+        if attempt < MAX_DELIVERY_ATTEMPTS {
+            sleep(backoff).await;
+            backoff = next_backoff(backoff);
+        }
+    }
+    warn!(
+        domain_id = event.domain_id,
+        event = payload.event,
+        "giving up on notification after {MAX_DELIVERY_ATTEMPTS} attempts"
+    );
+}
+
+/// Doubles the retry backoff, matching the delay `deliver` applies between
+/// attempts. Split out so the progression can be unit tested without an
+/// actual HTTP call.
+fn next_backoff(current: Duration) -> Duration {
+    current * 2
+}
+
+/// How often a watcher spawned by [`watch_and_notify`] polls the db for a
+/// newly committed row.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Polls `poll` for the most recently committed row of `event` and, each
+/// time it changes, pushes a [`NotificationEvent`] through `handle`. Used by
+/// `build_message_indexer` and the `spawn_sync_task!`-generated indexers to
+/// notify after a batch is committed to `HyperlaneSqlDb`, since the
+/// underlying `ChainContractSync::sync` future doesn't expose a per-batch
+/// callback to hook directly.
+pub async fn watch_and_notify<F, Fut>(handle: NotifierHandle, domain_id: u32, event: EventKind, mut poll: F)
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = eyre::Result<Option<(String, u64)>>>,
+{
+    let mut last_seen = None;
+    loop {
+        sleep(WATCH_POLL_INTERVAL).await;
+        if let Ok(Some((item_id, block_number))) = poll().await {
+            if last_seen.as_ref() != Some(&item_id) {
+                handle.notify(NotificationEvent {
+                    domain_id,
+                    event,
+                    item_id: item_id.clone(),
+                    block_number,
+                });
+                last_seen = Some(item_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_each_attempt() {
+        let mut backoff = INITIAL_RETRY_BACKOFF;
+        let mut seen = vec![backoff];
+        for _ in 0..(MAX_DELIVERY_ATTEMPTS - 1) {
+            backoff = next_backoff(backoff);
+            seen.push(backoff);
+        }
+        for pair in seen.windows(2) {
+            assert_eq!(pair[1], pair[0] * 2);
+        }
+    }
+
+    #[test]
+    fn remote_notifier_filters_by_subscribed_events() {
+        let notifier = RemoteNotifier {
+            webhook_url: "https://example.com/hook".to_string(),
+            auth_token: "secret".to_string(),
+            events: vec![EventKind::MessageDispatch],
+        };
+        assert!(notifier.wants(EventKind::MessageDispatch));
+        assert!(!notifier.wants(EventKind::MessageDelivery));
+    }
+
+    #[test]
+    fn notifier_config_parses_flattened_domain_map() {
+        let json = r#"{
+            "1": {"webhook_url": "https://a", "auth_token": "t1", "events": ["message_dispatch"]},
+            "2": {"webhook_url": "https://b", "auth_token": "t2", "events": ["gas_payment"]}
+        }"#;
+        let config: NotifierConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.get(1).unwrap().webhook_url, "https://a");
+        assert_eq!(config.get(2).unwrap().webhook_url, "https://b");
+        assert!(config.get(3).is_none());
+    }
+}
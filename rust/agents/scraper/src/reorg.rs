@@ -0,0 +1,256 @@
+use std::{ops::Range, time::Duration};
+
+use hyperlane_core::{H256, HyperlaneDomain};
+use prometheus::IntCounter;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::chain_scraper::HyperlaneSqlDb;
+
+/// Default rewind depth used when a chain has no override configured. This
+/// is scraper-local config (see `ScraperSettings::reorg_rewind_depth`), not
+/// `hyperlane_base::settings::IndexSettings`: that type is owned by a
+/// different crate and isn't something this series can add fields to.
+pub const DEFAULT_REORG_REWIND_DEPTH: u32 = 64;
+
+/// How often a running worker re-checks for a reorg against the live chain,
+/// on top of the check already run every time the worker (re)starts.
+const REORG_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Tracks the block number/hash pair for the current indexing tip, so it
+/// can tell a stale local record apart from a reorg actually observed on
+/// chain.
+#[derive(Debug, Clone, Default)]
+pub struct ReorgTracker {
+    tip: Option<(u32, H256)>,
+    /// How many blocks to walk backwards, at most, when searching for the
+    /// last common ancestor.
+    rewind_depth: u32,
+}
+
+/// A reorg was detected at `tip_number`: the parent hash recorded for the
+/// tip no longer matches the chain's current canonical hash for the block
+/// below it, so indexing must walk back to the last common ancestor and
+/// re-index forward from there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReorgDetected {
+    pub tip_number: u32,
+}
+
+impl ReorgTracker {
+    pub fn new(rewind_depth: u32) -> Self {
+        Self {
+            tip: None,
+            rewind_depth,
+        }
+    }
+
+    pub fn rewind_depth(&self) -> u32 {
+        self.rewind_depth
+    }
+
+    /// Record the block number/hash last persisted to `HyperlaneSqlDb`.
+    pub fn advance(&mut self, number: u32, hash: H256) {
+        self.tip = Some((number, hash));
+    }
+
+    /// Compares the parent hash this chain recorded for its indexed tip
+    /// against the chain's current canonical hash for the block below it.
+    /// Returns `None` when there's no tip yet (first check since startup)
+    /// or the two agree.
+    pub fn detect_reorg(
+        &self,
+        local_parent_hash: H256,
+        canonical_parent_hash: H256,
+    ) -> Option<ReorgDetected> {
+        let (tip_number, _) = self.tip?;
+        (local_parent_hash != canonical_parent_hash).then_some(ReorgDetected { tip_number })
+    }
+
+    /// Replace the tracked tip after a rewind, so later reorgs are reported
+    /// against the block rewound to instead of the orphaned tip.
+    pub fn reset_after_rewind(&mut self, rewound_to: u32, hash: H256) {
+        self.tip = Some((rewound_to, hash));
+    }
+}
+
+/// Given the local and canonical hash at each height below the tip, in
+/// descending order, returns the highest height where they still agree --
+/// the last common ancestor to safely resume indexing from. Falls back to
+/// `floor` (the height just below the deepest one `candidates` covers;
+/// candidates at or below it are ignored) if none of them match,
+/// meaning the reorg is deeper than this chain's configured rewind depth.
+pub fn find_common_ancestor(candidates: &[(u32, H256, H256)], floor: u32) -> u32 {
+    for &(height, local, canonical) in candidates {
+        if height <= floor {
+            break;
+        }
+        if local == canonical {
+            return height;
+        }
+    }
+    floor
+}
+
+/// Delete the orphaned message/delivery/gas-payment rows covering
+/// `window` from the SQL DB inside a single transaction, so a rewind never
+/// leaves partially-orphaned data behind if it's interrupted partway
+/// through.
+pub async fn rewind(db: &HyperlaneSqlDb, domain_id: u32, window: Range<u32>) -> eyre::Result<()> {
+    warn!(
+        domain_id,
+        from = window.start,
+        to = window.end,
+        "reorg detected, deleting orphaned rows"
+    );
+    db.delete_indexed_range(window.clone()).await?;
+    info!(
+        domain_id,
+        rewound_to = window.start,
+        "rewind complete, resuming forward indexing"
+    );
+    Ok(())
+}
+
+/// Checks `db`'s current tip against the chain's live canonical hashes,
+/// rewinding if a reorg is found. Called once every time a sync worker
+/// (re)starts, and on a timer by [`watch_for_reorgs`] while it keeps
+/// running, since `sync()` itself has no per-batch hook to check more
+/// granularly.
+pub async fn check_and_rewind(
+    db: &HyperlaneSqlDb,
+    domain: &HyperlaneDomain,
+    tracker: &Mutex<ReorgTracker>,
+    reorgs_detected: &IntCounter,
+) -> eyre::Result<()> {
+    let Some((number, hash)) = db.last_indexed_block().await? else {
+        return Ok(());
+    };
+    let mut tracker = tracker.lock().await;
+    let Some(local_parent_hash) = db.block_parent_hash(number).await? else {
+        tracker.advance(number, hash);
+        return Ok(());
+    };
+    let canonical_parent_hash = db.canonical_block_hash(number.saturating_sub(1)).await?;
+
+    if tracker
+        .detect_reorg(local_parent_hash, canonical_parent_hash)
+        .is_none()
+    {
+        tracker.advance(number, hash);
+        return Ok(());
+    }
+
+    reorgs_detected.inc();
+    let rewind_depth = tracker.rewind_depth();
+    let floor = number.saturating_sub(rewind_depth);
+
+    // Walk backwards from the tip comparing this chain's locally indexed
+    // hash against the chain's live canonical hash at each height, rather
+    // than jumping back a fixed distance: the first height where they agree
+    // is the actual last common ancestor, and a reorg deeper than
+    // `rewind_depth` is reported honestly instead of silently resuming from
+    // a block that may itself still be on the orphaned fork.
+    let mut candidates = Vec::new();
+    let mut height = number.saturating_sub(1);
+    while height > floor {
+        if let Some(local) = db.block_hash(height).await? {
+            let canonical = db.canonical_block_hash(height).await?;
+            candidates.push((height, local, canonical));
+        }
+        height = height.saturating_sub(1);
+    }
+    let common_ancestor = find_common_ancestor(&candidates, floor);
+    if common_ancestor == floor {
+        warn!(
+            domain = %domain.name(),
+            floor,
+            tip = number,
+            "reorg appears deeper than the configured rewind depth, rewinding to the depth limit anyway"
+        );
+    }
+
+    rewind(db, domain.id(), common_ancestor + 1..number + 1).await?;
+    let rewound_hash = db.block_hash(common_ancestor).await?.unwrap_or(hash);
+    tracker.reset_after_rewind(common_ancestor, rewound_hash);
+    Ok(())
+}
+
+/// Runs [`check_and_rewind`] on a fixed interval for as long as the task
+/// lives, so a healthy worker that never restarts still notices a reorg
+/// instead of only checking once at startup. Meant to be spawned alongside
+/// a sync worker and aborted when that worker returns. A rewind made here
+/// deletes the orphaned rows but does not move the running sync's cursor.
+pub async fn watch_for_reorgs(
+    db: HyperlaneSqlDb,
+    domain: HyperlaneDomain,
+    tracker: std::sync::Arc<Mutex<ReorgTracker>>,
+    reorgs_detected: IntCounter,
+) {
+    loop {
+        tokio::time::sleep(REORG_CHECK_INTERVAL).await;
+        if let Err(err) = check_and_rewind(&db, &domain, &tracker, &reorgs_detected).await {
+            warn!(%err, domain = %domain.name(), "periodic reorg check failed");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(byte: u8) -> H256 {
+        H256::repeat_byte(byte)
+    }
+
+    #[test]
+    fn no_reorg_detected_before_first_advance() {
+        let tracker = ReorgTracker::new(10);
+        assert!(tracker.detect_reorg(hash(1), hash(1)).is_none());
+    }
+
+    #[test]
+    fn no_reorg_when_parent_hash_matches_canonical() {
+        let mut tracker = ReorgTracker::new(10);
+        tracker.advance(100, hash(1));
+        assert!(tracker.detect_reorg(hash(2), hash(2)).is_none());
+    }
+
+    #[test]
+    fn reorg_detected_when_parent_hash_diverges_from_canonical() {
+        let mut tracker = ReorgTracker::new(10);
+        tracker.advance(100, hash(1));
+        let detected = tracker
+            .detect_reorg(hash(2), hash(3))
+            .expect("reorg should be detected");
+        assert_eq!(detected, ReorgDetected { tip_number: 100 });
+    }
+
+    #[test]
+    fn reset_after_rewind_clears_the_old_tip() {
+        let mut tracker = ReorgTracker::new(10);
+        tracker.advance(100, hash(1));
+        tracker.reset_after_rewind(90, hash(3));
+        assert!(tracker.detect_reorg(hash(3), hash(3)).is_none());
+        assert_eq!(
+            tracker.detect_reorg(hash(1), hash(2)),
+            Some(ReorgDetected { tip_number: 90 })
+        );
+    }
+
+    #[test]
+    fn common_ancestor_is_the_highest_height_that_still_matches() {
+        let candidates = vec![
+            (99, hash(9), hash(200)),
+            (98, hash(8), hash(8)),
+            (97, hash(7), hash(7)),
+        ];
+        assert_eq!(find_common_ancestor(&candidates, 90), 98);
+    }
+
+    #[test]
+    fn common_ancestor_falls_back_to_floor_when_nothing_matches() {
+        let candidates = vec![(99, hash(9), hash(200)), (98, hash(8), hash(201))];
+        assert_eq!(find_common_ancestor(&candidates, 97), 97);
+    }
+}
@@ -0,0 +1,93 @@
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+use hyperlane_base::{
+    settings::{ChainConf, Settings},
+    ChainContractSync, ContractSyncMetrics, CoreMetrics, HyperlaneAgentCore,
+};
+use hyperlane_core::{HyperlaneDomain, HyperlaneMessage, HyperlaneProvider};
+use serde::Deserialize;
+
+/// Top-level settings for the scraper agent.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScraperSettings {
+    #[serde(flatten)]
+    pub(crate) base: Settings,
+    /// Connection string for the scraper's own database (a postgres URL in
+    /// production; `ScraperDb::connect` also accepts `sqlite::memory:` for
+    /// benchmarks).
+    pub db: String,
+    pub chains_to_scrape: Vec<HyperlaneDomain>,
+    /// Path to the per-chain webhook notifier config (see
+    /// `crate::notifier::NotifierConfig`).
+    pub notifier_config_path: PathBuf,
+    /// Per-chain override for how many blocks a reorg may walk back before
+    /// giving up; keyed by chain name. Chains with no entry fall back to
+    /// `reorg::DEFAULT_REORG_REWIND_DEPTH`.
+    #[serde(default)]
+    reorg_rewind_depths: HashMap<String, u32>,
+}
+
+impl ScraperSettings {
+    pub fn build_hyperlane_core(&self, metrics: Arc<CoreMetrics>) -> HyperlaneAgentCore {
+        self.base.build_hyperlane_core(metrics)
+    }
+
+    pub fn chain_setup(&self, domain: &HyperlaneDomain) -> eyre::Result<&ChainConf> {
+        self.base.chain_setup(domain)
+    }
+
+    pub async fn build_provider(
+        &self,
+        domain: &HyperlaneDomain,
+        metrics: &CoreMetrics,
+    ) -> eyre::Result<Box<dyn HyperlaneProvider>> {
+        self.base.build_provider(domain, metrics).await
+    }
+
+    /// The configured reorg rewind depth for `domain`, if this deployment
+    /// set one in `reorg_rewind_depths`.
+    pub fn reorg_rewind_depth(&self, domain: &HyperlaneDomain) -> Option<u32> {
+        self.reorg_rewind_depths.get(domain.name()).copied()
+    }
+
+    pub async fn build_message_indexer(
+        &self,
+        domain: &HyperlaneDomain,
+        metrics: &Arc<CoreMetrics>,
+        contract_sync_metrics: &Arc<ContractSyncMetrics>,
+        store: Arc<crate::chain_scraper::HyperlaneSqlDb>,
+    ) -> eyre::Result<ChainContractSync<HyperlaneMessage>> {
+        self.base
+            .contract_sync::<HyperlaneMessage, _>(domain, metrics, contract_sync_metrics, store)
+            .await
+    }
+
+    pub async fn build_delivery_indexer(
+        &self,
+        domain: &HyperlaneDomain,
+        metrics: &Arc<CoreMetrics>,
+        contract_sync_metrics: &Arc<ContractSyncMetrics>,
+        store: Arc<crate::chain_scraper::HyperlaneSqlDb>,
+    ) -> eyre::Result<ChainContractSync<hyperlane_core::Delivery>> {
+        self.base
+            .contract_sync::<hyperlane_core::Delivery, _>(domain, metrics, contract_sync_metrics, store)
+            .await
+    }
+
+    pub async fn build_interchain_gas_payment_indexer(
+        &self,
+        domain: &HyperlaneDomain,
+        metrics: &Arc<CoreMetrics>,
+        contract_sync_metrics: &Arc<ContractSyncMetrics>,
+        store: Arc<crate::chain_scraper::HyperlaneSqlDb>,
+    ) -> eyre::Result<ChainContractSync<hyperlane_core::InterchainGasPayment>> {
+        self.base
+            .contract_sync::<hyperlane_core::InterchainGasPayment, _>(
+                domain,
+                metrics,
+                contract_sync_metrics,
+                store,
+            )
+            .await
+    }
+}
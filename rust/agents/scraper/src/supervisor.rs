@@ -0,0 +1,177 @@
+use std::{future::Future, time::Duration};
+
+use hyperlane_base::CoreMetrics;
+use prometheus::IntGaugeVec;
+use tokio::{sync::watch, task::JoinHandle};
+use tracing::{error, info_span, instrument::Instrumented, warn, Instrument};
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+/// How long a worker's in-progress sync is given to finish on its own once
+/// the shutdown signal fires, before it's dropped outright.
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Doubles `current`, capped at `MAX_BACKOFF`. Split out from `supervise` so
+/// the progression can be unit tested without spawning any tasks.
+fn next_backoff(current: Duration) -> Duration {
+    (current * 2).min(MAX_BACKOFF)
+}
+
+/// Owns every spawned `ChainContractSync` future, restarting any indexer
+/// that exits with an error or panic with exponential backoff, while
+/// leaving its sibling chains untouched. Also owns the shutdown signal: on
+/// SIGTERM `shutdown` flips a `watch::Sender<bool>` shared by every worker,
+/// so in-flight batches can finish flushing to `HyperlaneSqlDb` before the
+/// task returns and is joined.
+#[derive(Debug)]
+pub struct BackgroundRunner {
+    shutdown_tx: watch::Sender<bool>,
+    restart_count: IntGaugeVec,
+    backoff_seconds: IntGaugeVec,
+}
+
+impl BackgroundRunner {
+    pub fn new(metrics: &CoreMetrics) -> eyre::Result<Self> {
+        let (shutdown_tx, _) = watch::channel(false);
+        let restart_count = metrics.new_int_gauge(
+            "scraper_indexer_restarts",
+            "Number of times a chain's indexer task has been restarted after an error or panic",
+            &["domain", "event_label"],
+        )?;
+        let backoff_seconds = metrics.new_int_gauge(
+            "scraper_indexer_backoff_seconds",
+            "Current restart backoff, in seconds, for a chain's indexer task",
+            &["domain", "event_label"],
+        )?;
+        Ok(Self {
+            shutdown_tx,
+            restart_count,
+            backoff_seconds,
+        })
+    }
+
+    /// A fresh shutdown receiver for a new worker; flipped to `true` by
+    /// [`BackgroundRunner::shutdown`].
+    pub fn shutdown_signal(&self) -> watch::Receiver<bool> {
+        self.shutdown_tx.subscribe()
+    }
+
+    /// Tell every supervised worker to wind down. Each worker's sync is
+    /// given up to `SHUTDOWN_GRACE_PERIOD` to finish and is then dropped,
+    /// whether or not a batch is still being written, and the worker
+    /// returns `Ok(())`.
+    pub fn shutdown(&self) {
+        let _ = self.shutdown_tx.send(true);
+    }
+
+    /// Spawn `make_worker` and keep restarting it with exponential backoff
+    /// whenever it returns an `Err` or panics, until it returns `Ok(())`
+    /// (which only happens once `shutdown` has been called).
+    pub fn supervise<F, Fut>(
+        &self,
+        domain: String,
+        event_label: &'static str,
+        make_worker: F,
+    ) -> Instrumented<JoinHandle<eyre::Result<()>>>
+    where
+        F: Fn(watch::Receiver<bool>) -> Fut + Send + 'static,
+        Fut: Future<Output = eyre::Result<()>> + Send + 'static,
+    {
+        let restart_count = self
+            .restart_count
+            .with_label_values(&[&domain, event_label]);
+        let backoff_gauge = self
+            .backoff_seconds
+            .with_label_values(&[&domain, event_label]);
+        let mut shutdown_rx = self.shutdown_signal();
+
+        tokio::spawn(async move {
+            let mut backoff = INITIAL_BACKOFF;
+            loop {
+                if *shutdown_rx.borrow() {
+                    return Ok(());
+                }
+
+                let worker = tokio::spawn(make_worker(shutdown_rx.clone()));
+                let outcome = worker.await;
+
+                if *shutdown_rx.borrow() {
+                    return Ok(());
+                }
+
+                match outcome {
+                    Ok(Ok(())) => return Ok(()),
+                    Ok(Err(err)) => {
+                        error!(%err, domain=%domain, event=%event_label, "indexer task exited with an error, restarting");
+                    }
+                    Err(join_err) => {
+                        error!(%join_err, domain=%domain, event=%event_label, "indexer task panicked, restarting");
+                    }
+                }
+
+                restart_count.inc();
+                backoff_gauge.set(backoff.as_secs() as i64);
+
+                tokio::select! {
+                    _ = tokio::time::sleep(backoff) => {}
+                    _ = shutdown_rx.changed() => {
+                        if *shutdown_rx.borrow() {
+                            return Ok(());
+                        }
+                    }
+                }
+                backoff = next_backoff(backoff);
+            }
+        })
+        .instrument(info_span!("BackgroundRunner", chain = %domain, event = event_label))
+    }
+}
+
+/// Waits for `sync` to finish, or for the shutdown signal to fire. The
+/// shutdown signal is never passed into `sync` itself, so `sync` has no way
+/// to know shutdown happened and cannot return early on its own: this is
+/// just giving whatever batch `sync` is in the middle of up to
+/// `SHUTDOWN_GRACE_PERIOD` to finish naturally before it's dropped outright,
+/// not a cooperative shutdown mechanism.
+pub async fn run_until_shutdown(
+    mut shutdown: watch::Receiver<bool>,
+    sync: impl Future<Output = eyre::Result<()>>,
+) -> eyre::Result<()> {
+    tokio::pin!(sync);
+    tokio::select! {
+        result = &mut sync => return result,
+        _ = shutdown.changed() => {}
+    }
+
+    match tokio::time::timeout(SHUTDOWN_GRACE_PERIOD, &mut sync).await {
+        Ok(result) => result,
+        Err(_) => {
+            warn!("sync task did not finish within the shutdown grace period, dropping it");
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let mut backoff = INITIAL_BACKOFF;
+        for _ in 0..4 {
+            let next = next_backoff(backoff);
+            assert_eq!(next, backoff * 2);
+            backoff = next;
+        }
+    }
+
+    #[test]
+    fn backoff_saturates_at_max() {
+        let mut backoff = MAX_BACKOFF;
+        for _ in 0..3 {
+            backoff = next_backoff(backoff);
+            assert_eq!(backoff, MAX_BACKOFF);
+        }
+    }
+}